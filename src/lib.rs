@@ -59,6 +59,12 @@
 //!    }
 //!    ```
 //!
+//! Deserialization also accepts a bare number, interpreted as a count of nanoseconds, and for
+//! non-human-readable formats (e.g. `bincode`, `msgpack`) a `DurationString` is serialized as a
+//! `u64` nanosecond count rather than a string. If a field is always expressed in a single unit
+//! (seconds, milliseconds, ...), use the `serde::seconds`/`serde::millis`/`serde::nanos` helper
+//! modules with `#[serde(with = "...")]` instead.
+//!
 #![cfg_attr(feature = "serde", doc = "```rust")]
 #![cfg_attr(not(feature = "serde"), doc = "```ignore")]
 //! ```
@@ -77,7 +83,7 @@
 //! ```
 
 #[cfg(feature = "serde")]
-use serde::de::Unexpected;
+use ::serde::de::Unexpected;
 use std::borrow::{Borrow, BorrowMut};
 use std::convert::TryFrom;
 #[cfg(feature = "serde")]
@@ -90,6 +96,10 @@ use std::str::FromStr;
 use std::time::Duration;
 
 const YEAR_IN_NANO: u128 = 31_556_926_000_000_000;
+// A "month" has no fixed length, so for the purposes of ISO 8601 parsing/formatting we define
+// it as 1/12th of `YEAR_IN_NANO`, matching the `xsd:duration` convention of deriving it from
+// the year rather than from a 30-day approximation.
+const MONTH_IN_NANO: u128 = YEAR_IN_NANO / 12;
 const WEEK_IN_NANO: u128 = 604_800_000_000_000;
 const DAY_IN_NANO: u128 = 86_400_000_000_000;
 const HOUR_IN_NANO: u128 = 3_600_000_000_000;
@@ -98,12 +108,6 @@ const SECOND_IN_NANO: u128 = 1_000_000_000;
 const MILLISECOND_IN_NANO: u128 = 1_000_000;
 const MICROSECOND_IN_NANO: u128 = 1000;
 
-const HOUR_IN_SECONDS: u32 = 3600;
-const MINUTE_IN_SECONDS: u32 = 60;
-const DAY_IN_SECONDS: u32 = 86_400;
-const WEEK_IN_SECONDS: u32 = 604_800;
-const YEAR_IN_SECONDS: u32 = 31_556_926;
-
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -111,6 +115,13 @@ pub enum Error {
     Format,
     Overflow,
     ParseInt(ParseIntError),
+    /// The unit following a number (e.g. the `x` in `1h30x15s`) is not one of
+    /// `ns|us|ms|s|m|h|d|w|y`. `position` is the index, in characters, of the unit's first
+    /// character within the (whitespace-stripped) input.
+    UnknownUnit { unit: String, position: usize },
+    /// A number was followed by the end of the input or by a character that is not a letter,
+    /// so no unit was found. `position` is the index, in characters, where the unit was expected.
+    MissingUnit { position: usize },
 }
 
 impl std::fmt::Display for Error {
@@ -122,6 +133,12 @@ impl std::fmt::Display for Error {
             ),
             Self::Overflow => write!(f, "number is too large to fit in target type"),
             Self::ParseInt(err) => write!(f, "{err}"),
+            Self::UnknownUnit { unit, position } => {
+                write!(f, "unknown time unit \"{unit}\" at position {position}")
+            }
+            Self::MissingUnit { position } => {
+                write!(f, "missing time unit at position {position}")
+            }
         }
     }
 }
@@ -129,7 +146,10 @@ impl std::fmt::Display for Error {
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            Self::Format | Self::Overflow => None,
+            Self::Format
+            | Self::Overflow
+            | Self::UnknownUnit { .. }
+            | Self::MissingUnit { .. } => None,
             Self::ParseInt(err) => Some(err),
         }
     }
@@ -141,6 +161,99 @@ impl From<ParseIntError> for Error {
     }
 }
 
+/// A single time unit recognised by the `[0-9]+(ns|us|ms|[smhdwy])` grammar, ordered from
+/// smallest to largest so that `Unit`s can be compared with `<`/`>`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Unit {
+    Ns,
+    Us,
+    Ms,
+    S,
+    M,
+    H,
+    D,
+    W,
+    Y,
+}
+
+impl Unit {
+    const fn nanos_per_unit(self) -> u128 {
+        match self {
+            Self::Ns => 1,
+            Self::Us => MICROSECOND_IN_NANO,
+            Self::Ms => MILLISECOND_IN_NANO,
+            Self::S => SECOND_IN_NANO,
+            Self::M => MINUTE_IN_NANO,
+            Self::H => HOUR_IN_NANO,
+            Self::D => DAY_IN_NANO,
+            Self::W => WEEK_IN_NANO,
+            Self::Y => YEAR_IN_NANO,
+        }
+    }
+
+    const fn suffix(self) -> &'static str {
+        match self {
+            Self::Ns => "ns",
+            Self::Us => "us",
+            Self::Ms => "ms",
+            Self::S => "s",
+            Self::M => "m",
+            Self::H => "h",
+            Self::D => "d",
+            Self::W => "w",
+            Self::Y => "y",
+        }
+    }
+
+    fn parse(unit: &str) -> Option<Self> {
+        match unit {
+            "ns" => Some(Self::Ns),
+            "us" => Some(Self::Us),
+            "ms" => Some(Self::Ms),
+            "s" => Some(Self::S),
+            "m" => Some(Self::M),
+            "h" => Some(Self::H),
+            "d" => Some(Self::D),
+            "w" => Some(Self::W),
+            "y" => Some(Self::Y),
+            _ => None,
+        }
+    }
+}
+
+/// Rejects a malformed decimal literal: more than one `.`, or a `.` with no digit before or
+/// after it (e.g. `"1."` or `".5"`). Shared by [`FromStr`](DurationString::from_str) and
+/// [`DurationString::parse_iso8601_segment`] so both accept the same `N.N` shape.
+fn validate_decimal_digits(digits: &str, dots: usize) -> Result<()> {
+    if dots > 1 || digits.starts_with('.') || digits.ends_with('.') {
+        return Err(Error::Format);
+    }
+    Ok(())
+}
+
+/// Computes `whole.frac * unit_in_nano`, rounded to the nearest nanosecond (ties rounding up),
+/// using only integer arithmetic so large inputs don't lose precision the way routing the
+/// value through `f64` would. `whole` and `frac` are the digit strings either side of the
+/// decimal point; either may be empty (e.g. `.5` or `1.`).
+fn fractional_nanos(whole: &str, frac: &str, unit_in_nano: u128) -> Result<u128> {
+    let whole: u128 = if whole.is_empty() { 0 } else { whole.parse()? };
+    let whole_nanos = whole.checked_mul(unit_in_nano).ok_or(Error::Overflow)?;
+    if frac.is_empty() {
+        return Ok(whole_nanos);
+    }
+
+    let frac_numerator: u128 = frac.parse()?;
+    let scale: u128 = 10u128
+        .checked_pow(u32::try_from(frac.len()).map_err(|_| Error::Overflow)?)
+        .ok_or(Error::Overflow)?;
+    let frac_numerator_nanos = frac_numerator
+        .checked_mul(unit_in_nano)
+        .ok_or(Error::Overflow)?;
+    let frac_nanos = (frac_numerator_nanos + scale / 2) / scale;
+
+    whole_nanos.checked_add(frac_nanos).ok_or(Error::Overflow)
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
 pub struct DurationString(Duration);
 
@@ -154,6 +267,239 @@ impl DurationString {
     pub fn from_string(duration: String) -> Result<Self> {
         DurationString::try_from(duration)
     }
+
+    /// Parses an ISO 8601 / `xsd:duration` formatted string, e.g. `P1Y2M3DT4H5M6S`, into a
+    /// [`DurationString`].
+    ///
+    /// The grammar is `P[nY][nM][nW][nD][T[nH][nM][nS]]`: a leading `P`, optional date
+    /// components in the order `Y`, `M`, `W`, `D`, then an optional `T` separator introducing
+    /// time components in the order `H`, `M`, `S`. Note that `M` means months before `T` and
+    /// minutes after `T`. At least one component must be present. The seconds field may carry
+    /// a decimal fraction, e.g. `PT1.5S`.
+    ///
+    /// `Y` and `M` (years/months) are converted using the fixed constants `YEAR_IN_NANO` and
+    /// one twelfth of it respectively, since `DurationString` has no concept of a calendar.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn from_iso8601(duration: &str) -> Result<Self> {
+        let duration = duration.trim();
+        let rest = duration.strip_prefix('P').ok_or(Error::Format)?;
+        let (date_part, time_part) = match rest.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (rest, None),
+        };
+
+        let date_components = Self::parse_iso8601_segment(date_part, &['Y', 'M', 'W', 'D'])?;
+        let time_components = match time_part {
+            Some(time) => {
+                let components = Self::parse_iso8601_segment(time, &['H', 'M', 'S'])?;
+                if components.is_empty() {
+                    // `T` with no time components is not a valid duration
+                    return Err(Error::Format);
+                }
+                Some(components)
+            }
+            None => None,
+        };
+        if date_components.is_empty() && time_components.is_none() {
+            // Bare `P` is not a valid duration
+            return Err(Error::Format);
+        }
+
+        let mut total_nanos: u128 = 0;
+        for (digits, unit) in date_components {
+            let unit_in_nano = match unit {
+                'Y' => YEAR_IN_NANO,
+                'M' => MONTH_IN_NANO,
+                'W' => WEEK_IN_NANO,
+                'D' => DAY_IN_NANO,
+                _ => unreachable!(),
+            };
+            total_nanos = total_nanos
+                .checked_add(Self::component_nanos(&digits, unit_in_nano)?)
+                .ok_or(Error::Overflow)?;
+        }
+        for (digits, unit) in time_components.into_iter().flatten() {
+            let unit_in_nano = match unit {
+                'H' => HOUR_IN_NANO,
+                'M' => MINUTE_IN_NANO,
+                'S' => SECOND_IN_NANO,
+                _ => unreachable!(),
+            };
+            total_nanos = total_nanos
+                .checked_add(Self::component_nanos(&digits, unit_in_nano)?)
+                .ok_or(Error::Overflow)?;
+        }
+
+        Ok(DurationString(Duration::from_nanos(
+            u64::try_from(total_nanos).map_err(|_| Error::Overflow)?,
+        )))
+    }
+
+    /// Converts a digit string captured by [`parse_iso8601_segment`](Self::parse_iso8601_segment)
+    /// (optionally containing a single decimal point) into nanoseconds, reusing the same
+    /// integer/rational arithmetic as [`FromStr`](Self::from_str) to avoid `f64` precision loss.
+    fn component_nanos(digits: &str, unit_in_nano: u128) -> Result<u128> {
+        match digits.split_once('.') {
+            Some((whole, frac)) => fractional_nanos(whole, frac, unit_in_nano),
+            None => {
+                let value: u128 = digits.parse()?;
+                value.checked_mul(unit_in_nano).ok_or(Error::Overflow)
+            }
+        }
+    }
+
+    /// Parses a single date or time segment of an ISO 8601 duration into `(digits, unit)`
+    /// pairs, enforcing that `allowed` units appear at most once and in the given order. Only
+    /// the `S` (seconds) unit may carry a decimal fraction, per `xsd:duration`; any other
+    /// component with a `.` is rejected, as is a malformed fraction (more than one `.`, or a
+    /// leading/trailing `.`), matching the validation [`FromStr`](Self::from_str) applies.
+    fn parse_iso8601_segment(segment: &str, allowed: &[char]) -> Result<Vec<(String, char)>> {
+        let chars: Vec<char> = segment.chars().collect();
+        let mut components = vec![];
+        let mut idx = 0;
+        let mut allowed_from = 0;
+        while idx < chars.len() {
+            let start = idx;
+            let mut dots = 0;
+            while idx < chars.len() && (chars[idx].is_ascii_digit() || chars[idx] == '.') {
+                if chars[idx] == '.' {
+                    dots += 1;
+                }
+                idx += 1;
+            }
+            if idx == start || idx == chars.len() {
+                return Err(Error::Format);
+            }
+            let digits: String = chars[start..idx].iter().collect();
+            validate_decimal_digits(&digits, dots)?;
+            let unit = chars[idx];
+            idx += 1;
+            if dots > 0 && unit != 'S' {
+                return Err(Error::Format);
+            }
+
+            let position = allowed[allowed_from..]
+                .iter()
+                .position(|candidate| *candidate == unit)
+                .ok_or(Error::Format)?;
+            allowed_from += position + 1;
+            components.push((digits, unit));
+        }
+        Ok(components)
+    }
+
+    /// Formats this duration using the ISO 8601 / `xsd:duration` grammar
+    /// (`P[nY][nM][nD][T[nH][nM][nS]]`), picking the largest non-zero components. A zero
+    /// duration is formatted as `PT0S`.
+    ///
+    /// Weeks are never emitted, since mixing `W` with `Y`/`M`/`D` in the same duration is
+    /// ambiguous; remaining whole days are always expressed with `D`.
+    #[must_use]
+    pub fn to_iso8601(&self) -> String {
+        let mut remaining = self.0.as_nanos();
+        if remaining == 0 {
+            return String::from("PT0S");
+        }
+
+        let mut date = String::new();
+        let years = remaining / YEAR_IN_NANO;
+        remaining %= YEAR_IN_NANO;
+        if years > 0 {
+            date += &format!("{years}Y");
+        }
+        let months = remaining / MONTH_IN_NANO;
+        remaining %= MONTH_IN_NANO;
+        if months > 0 {
+            date += &format!("{months}M");
+        }
+        let days = remaining / DAY_IN_NANO;
+        remaining %= DAY_IN_NANO;
+        if days > 0 {
+            date += &format!("{days}D");
+        }
+
+        let hours = remaining / HOUR_IN_NANO;
+        remaining %= HOUR_IN_NANO;
+        let minutes = remaining / MINUTE_IN_NANO;
+        remaining %= MINUTE_IN_NANO;
+        let seconds_nanos = remaining;
+
+        let mut time = String::new();
+        if hours > 0 {
+            time += &format!("{hours}H");
+        }
+        if minutes > 0 {
+            time += &format!("{minutes}M");
+        }
+        if seconds_nanos > 0 {
+            let whole_seconds = seconds_nanos / SECOND_IN_NANO;
+            let fraction = seconds_nanos % SECOND_IN_NANO;
+            if fraction > 0 {
+                let fraction = format!("{fraction:09}");
+                let fraction = fraction.trim_end_matches('0');
+                time += &format!("{whole_seconds}.{fraction}S");
+            } else {
+                time += &format!("{whole_seconds}S");
+            }
+        }
+
+        let mut out = format!("P{date}");
+        if !time.is_empty() {
+            out += "T";
+            out += &time;
+        }
+        out
+    }
+
+    /// Decomposes this duration greatest-unit-first into a concatenation such as `1h30m15s`,
+    /// still parseable by [`FromStr`](Self::from_str). Unlike [`From<DurationString> for
+    /// String`](#impl-From<DurationString>-for-String), which collapses to a single largest
+    /// *exact* unit, every non-zero unit down to nanoseconds is emitted.
+    #[must_use]
+    pub fn to_string_compact(&self) -> String {
+        self.format_components(usize::MAX, Unit::Ns)
+    }
+
+    /// Like [`to_string_compact`](Self::to_string_compact), but emits at most
+    /// `max_components` components and truncates (rounds down, discarding the remainder)
+    /// anything smaller than `min_unit`. For example `1h30m15s` formatted with
+    /// `(2, Unit::M)` yields `1h30m`.
+    #[must_use]
+    pub fn format_components(&self, max_components: usize, min_unit: Unit) -> String {
+        const UNITS: [Unit; 9] = [
+            Unit::Y,
+            Unit::W,
+            Unit::D,
+            Unit::H,
+            Unit::M,
+            Unit::S,
+            Unit::Ms,
+            Unit::Us,
+            Unit::Ns,
+        ];
+
+        let mut remaining = self.0.as_nanos();
+        let mut out = String::new();
+        let mut emitted = 0;
+        for unit in UNITS {
+            if remaining == 0 || emitted >= max_components || unit < min_unit {
+                break;
+            }
+            let unit_in_nano = unit.nanos_per_unit();
+            let value = remaining / unit_in_nano;
+            remaining %= unit_in_nano;
+            if value > 0 {
+                out += &value.to_string();
+                out += unit.suffix();
+                emitted += 1;
+            }
+        }
+        if out.is_empty() {
+            out += "0";
+            out += min_unit.suffix();
+        }
+        out
+    }
 }
 
 impl std::fmt::Display for DurationString {
@@ -218,53 +564,66 @@ impl FromStr for DurationString {
     type Err = Error;
 
     fn from_str(duration: &str) -> std::result::Result<Self, Self::Err> {
-        let duration: Vec<char> = duration.chars().filter(|c| !c.is_whitespace()).collect();
-        let mut grouped_durations: Vec<(Vec<char>, Vec<char>)> = vec![(vec![], vec![])];
-        for i in 0..duration.len() {
-            // Vector initialised with a starting element so unwraps should never panic
-            if duration[i].is_numeric() {
-                grouped_durations.last_mut().unwrap().0.push(duration[i]);
-            } else {
-                grouped_durations.last_mut().unwrap().1.push(duration[i]);
-            }
-            if i != duration.len() - 1 && !duration[i].is_numeric() && duration[i + 1].is_numeric()
-            {
-                // move to next group
-                grouped_durations.push((vec![], vec![]));
-            }
-        }
-        if grouped_durations.is_empty() {
+        let chars: Vec<char> = duration.chars().filter(|c| !c.is_whitespace()).collect();
+        if chars.is_empty() {
             // `duration` either contains no numbers or no letters
             return Err(Error::Format);
         }
-        let mut total_duration = Duration::new(0, 0);
-        for (period, format) in grouped_durations {
-            let period = match period.iter().collect::<String>().parse::<u64>() {
-                Ok(period) => Ok(period),
-                Err(err) => Err(Error::ParseInt(err)),
-            }?;
-            let multiply_period = |multiplier: u32| -> std::result::Result<Duration, Self::Err> {
-                Duration::from_secs(period)
-                    .checked_mul(multiplier)
-                    .ok_or(Error::Overflow)
+
+        let mut total_nanos: u128 = 0;
+        let mut idx = 0;
+        while idx < chars.len() {
+            let digits_start = idx;
+            let mut dots = 0;
+            while idx < chars.len() && (chars[idx].is_numeric() || chars[idx] == '.') {
+                if chars[idx] == '.' {
+                    dots += 1;
+                }
+                idx += 1;
+            }
+            if idx == digits_start {
+                // A unit (or garbage) where a number was expected
+                return Err(Error::Format);
+            }
+            let period: String = chars[digits_start..idx].iter().collect();
+            validate_decimal_digits(&period, dots)?;
+
+            let unit_start = idx;
+            while idx < chars.len() && chars[idx].is_alphabetic() {
+                idx += 1;
+            }
+            if idx == unit_start {
+                return Err(Error::MissingUnit {
+                    position: unit_start,
+                });
+            }
+            let unit_str: String = chars[unit_start..idx].iter().collect();
+            let unit = Unit::parse(&unit_str).ok_or(Error::UnknownUnit {
+                unit: unit_str,
+                position: unit_start,
+            })?;
+            let unit_in_nano = unit.nanos_per_unit();
+
+            let period_nanos: u128 = if dots == 1 {
+                // A decimal point was present, e.g. `1.5h` or `0.5s`. Split into an integer
+                // part and a fractional part and multiply each in integer/rational
+                // arithmetic, rather than routing the whole value through `f64`, which loses
+                // precision for realistic inputs (e.g. `0.268y`).
+                let (whole, frac) = period.split_once('.').unwrap();
+                fractional_nanos(whole, frac, unit_in_nano)?
+            } else {
+                let period: u64 = period.parse()?;
+                u128::from(period)
+                    .checked_mul(unit_in_nano)
+                    .ok_or(Error::Overflow)?
             };
-            let period_duration = match format.iter().collect::<String>().as_ref() {
-                "ns" => Ok(Duration::from_nanos(period)),
-                "us" => Ok(Duration::from_micros(period)),
-                "ms" => Ok(Duration::from_millis(period)),
-                "s" => Ok(Duration::from_secs(period)),
-                "m" => multiply_period(MINUTE_IN_SECONDS),
-                "h" => multiply_period(HOUR_IN_SECONDS),
-                "d" => multiply_period(DAY_IN_SECONDS),
-                "w" => multiply_period(WEEK_IN_SECONDS),
-                "y" => multiply_period(YEAR_IN_SECONDS),
-                _ => Err(Error::Format),
-            }?;
-            total_duration = total_duration
-                .checked_add(period_duration)
+            total_nanos = total_nanos
+                .checked_add(period_nanos)
                 .ok_or(Error::Overflow)?;
         }
-        Ok(DurationString(total_duration))
+        Ok(DurationString(Duration::from_nanos(
+            u64::try_from(total_nanos).map_err(|_| Error::Overflow)?,
+        )))
     }
 }
 
@@ -294,66 +653,203 @@ impl BorrowMut<Duration> for DurationString {
     }
 }
 
+/// Error from [`exact_units`]: either `nanos` isn't a whole number of `nanos_per_unit`, or the
+/// resulting count of units doesn't fit in a `u64`.
+#[cfg(feature = "serde")]
+#[derive(Debug, PartialEq, Eq)]
+enum ExactUnitsError {
+    NotExact,
+    Overflow,
+}
+
+/// Converts `nanos` to a count of `nanos_per_unit`-sized units, failing rather than silently
+/// truncating when `nanos` isn't an exact multiple (e.g. `1500ms` through the `seconds`
+/// with-module) or when the unit count doesn't fit in a `u64`.
+#[cfg(feature = "serde")]
+fn exact_units(nanos: u128, nanos_per_unit: u128) -> std::result::Result<u64, ExactUnitsError> {
+    if !nanos.is_multiple_of(nanos_per_unit) {
+        return Err(ExactUnitsError::NotExact);
+    }
+    u64::try_from(nanos / nanos_per_unit).map_err(|_| ExactUnitsError::Overflow)
+}
+
+/// Visitor for [`DurationString`], accepting either a human string (e.g. `"1m"`) or a bare
+/// number expressed in `nanos_per_unit` nanoseconds, so a field can be deserialized from
+/// `"1m"` just as well as from a raw `60` when used through the [`serde`](mod@crate::serde)
+/// helper modules.
 #[cfg(feature = "serde")]
 struct DurationStringVisitor {
     marker: PhantomData<fn() -> DurationString>,
+    nanos_per_unit: u64,
 }
 
 #[cfg(feature = "serde")]
 impl DurationStringVisitor {
-    fn new() -> Self {
+    fn new(nanos_per_unit: u64) -> Self {
         Self {
             marker: PhantomData,
+            nanos_per_unit,
         }
     }
 }
 
 #[cfg(feature = "serde")]
-impl<'de> serde::de::Visitor<'de> for DurationStringVisitor {
+impl<'de> ::serde::de::Visitor<'de> for DurationStringVisitor {
     type Value = DurationString;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("string")
+        formatter.write_str("a duration string or a number")
     }
 
     fn visit_str<E>(self, string: &str) -> std::result::Result<Self::Value, E>
     where
-        E: serde::de::Error,
+        E: ::serde::de::Error,
     {
         match DurationString::from_string(string.to_string()) {
             Ok(d) => Ok(d),
-            Err(s) => Err(serde::de::Error::invalid_value(
+            Err(s) => Err(::serde::de::Error::invalid_value(
                 Unexpected::Str(&s.to_string()),
                 &self,
             )),
         }
     }
+
+    fn visit_u64<E>(self, value: u64) -> std::result::Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        let nanos = value
+            .checked_mul(self.nanos_per_unit)
+            .ok_or_else(|| E::custom("duration value overflows u64 nanoseconds"))?;
+        Ok(DurationString(Duration::from_nanos(nanos)))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> std::result::Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        let value =
+            u64::try_from(value).map_err(|_| E::custom("duration value must not be negative"))?;
+        self.visit_u64(value)
+    }
+
+    fn visit_f64<E>(self, value: f64) -> std::result::Result<Self::Value, E>
+    where
+        E: ::serde::de::Error,
+    {
+        if value < 0.0 {
+            return Err(E::custom("duration value must not be negative"));
+        }
+        let nanos = (value * self.nanos_per_unit as f64).round();
+        if !nanos.is_finite() || nanos >= u64::MAX as f64 {
+            return Err(E::custom("duration value overflows u64 nanoseconds"));
+        }
+        Ok(DurationString(Duration::from_nanos(nanos as u64)))
+    }
 }
 
 #[cfg(feature = "serde")]
-impl<'de> serde::Deserialize<'de> for DurationString {
+impl<'de> ::serde::Deserialize<'de> for DurationString {
     fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
     where
-        D: serde::Deserializer<'de>,
+        D: ::serde::Deserializer<'de>,
     {
-        deserializer.deserialize_str(DurationStringVisitor::new())
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(DurationStringVisitor::new(1))
+        } else {
+            deserializer.deserialize_u64(DurationStringVisitor::new(1))
+        }
     }
 }
 
 #[cfg(feature = "serde")]
-impl serde::Serialize for DurationString {
+impl ::serde::Serialize for DurationString {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
-        S: serde::Serializer,
+        S: ::serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_string())
+        } else {
+            let nanos = u64::try_from(self.0.as_nanos())
+                .map_err(|_| ::serde::ser::Error::custom("duration value overflows u64 nanoseconds"))?;
+            serializer.serialize_u64(nanos)
+        }
+    }
+}
+
+/// `#[serde(with = "...")]` helper modules that (de)serialize a [`DurationString`] from either
+/// a human string (e.g. `"1m"`) or a bare number expressed in a fixed base unit, mirroring the
+/// `DurationSeconds`/`DurationMilliSeconds` helpers in `serde_with`.
+#[cfg(feature = "serde")]
+pub mod serde {
+    macro_rules! duration_string_serde_module {
+        ($module:ident, $nanos_per_unit:expr) => {
+            #[doc = concat!(
+                "(De)serializes a [`DurationString`](crate::DurationString) as a human string \
+                 or a number of `",
+                stringify!($module),
+                "`."
+            )]
+            pub mod $module {
+                use crate::{DurationString, DurationStringVisitor};
+
+                #[allow(clippy::missing_errors_doc)]
+                pub fn serialize<S>(
+                    duration: &DurationString,
+                    serializer: S,
+                ) -> std::result::Result<S::Ok, S::Error>
+                where
+                    S: ::serde::Serializer,
+                {
+                    if serializer.is_human_readable() {
+                        serializer.serialize_str(&duration.to_string())
+                    } else {
+                        let units = crate::exact_units(
+                            duration.as_nanos(),
+                            u128::from($nanos_per_unit),
+                        )
+                        .map_err(|err| match err {
+                            crate::ExactUnitsError::NotExact => ::serde::ser::Error::custom(
+                                concat!(
+                                    "duration value is not a whole number of ",
+                                    stringify!($module)
+                                ),
+                            ),
+                            crate::ExactUnitsError::Overflow => {
+                                ::serde::ser::Error::custom("duration value overflows u64")
+                            }
+                        })?;
+                        serializer.serialize_u64(units)
+                    }
+                }
+
+                #[allow(clippy::missing_errors_doc)]
+                pub fn deserialize<'de, D>(
+                    deserializer: D,
+                ) -> std::result::Result<DurationString, D::Error>
+                where
+                    D: ::serde::Deserializer<'de>,
+                {
+                    if deserializer.is_human_readable() {
+                        deserializer.deserialize_any(DurationStringVisitor::new($nanos_per_unit))
+                    } else {
+                        deserializer.deserialize_u64(DurationStringVisitor::new($nanos_per_unit))
+                    }
+                }
+            }
+        };
     }
+
+    duration_string_serde_module!(seconds, crate::SECOND_IN_NANO as u64);
+    duration_string_serde_module!(millis, crate::MILLISECOND_IN_NANO as u64);
+    duration_string_serde_module!(nanos, 1u64);
 }
 #[cfg(test)]
 mod tests {
     use super::*;
     #[cfg(feature = "serde")]
-    use serde::{Deserialize, Serialize};
+    use ::serde::{Deserialize, Serialize};
 
     #[cfg(feature = "serde")]
     #[derive(Serialize, Deserialize)]
@@ -382,6 +878,105 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_trait_from_number_as_nanos() {
+        let s = r#"{"d":60}"#;
+        let v: SerdeSupport = serde_json::from_str(s).unwrap();
+        assert_eq!(v.d.to_string(), "60ns");
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Serialize, Deserialize)]
+    struct SerdeSupportSeconds {
+        #[serde(with = "crate::serde::seconds")]
+        d: DurationString,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_seconds_module_human_readable() {
+        let s = SerdeSupportSeconds {
+            d: DurationString::from_string(String::from("1m")).unwrap(),
+        };
+        assert_eq!(r#"{"d":"1m"}"#, serde_json::to_string(&s).unwrap());
+
+        let deserialized: SerdeSupportSeconds = serde_json::from_str(r#"{"d":60}"#).unwrap();
+        assert_eq!(deserialized.d.to_string(), "1m");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_visit_u64_overflow() {
+        let visitor = DurationStringVisitor::new(crate::SECOND_IN_NANO as u64);
+        let err = ::serde::de::Visitor::visit_u64::<::serde::de::value::Error>(
+            visitor,
+            u64::MAX,
+        )
+        .expect_err("a huge second count should overflow u64 nanoseconds");
+        assert!(err.to_string().contains("overflows"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_visit_i64_negative() {
+        let visitor = DurationStringVisitor::new(1);
+        let err = ::serde::de::Visitor::visit_i64::<::serde::de::value::Error>(visitor, -1)
+            .expect_err("a negative value should be rejected");
+        assert!(err.to_string().contains("negative"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_visit_f64_negative() {
+        let visitor = DurationStringVisitor::new(1);
+        let err = ::serde::de::Visitor::visit_f64::<::serde::de::value::Error>(visitor, -1.0)
+            .expect_err("a negative value should be rejected");
+        assert!(err.to_string().contains("negative"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_visit_f64_overflow() {
+        let visitor = DurationStringVisitor::new(1);
+        let err = ::serde::de::Visitor::visit_f64::<::serde::de::value::Error>(
+            visitor,
+            99_999_999_999_999_999_999_999.0,
+        )
+        .expect_err("a huge float value should overflow u64 nanoseconds, not saturate");
+        assert!(err.to_string().contains("overflows"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_exact_units_rejects_non_exact_multiple() {
+        // 1500ms is not a whole number of seconds: must error, not truncate to `1`.
+        assert!(matches!(
+            exact_units(1_500_000_000, crate::SECOND_IN_NANO),
+            Err(ExactUnitsError::NotExact)
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_exact_units_exact_multiple() {
+        assert_eq!(exact_units(2_000_000_000, crate::SECOND_IN_NANO), Ok(2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Serialize, Deserialize)]
+    struct SerdeSupportMillis {
+        #[serde(with = "crate::serde::millis")]
+        d: DurationString,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_millis_module_human_readable() {
+        let deserialized: SerdeSupportMillis = serde_json::from_str(r#"{"d":1500}"#).unwrap();
+        assert_eq!(Duration::from(deserialized.d), Duration::from_millis(1500));
+    }
+
     #[test]
     fn test_string_int_overflow() {
         DurationString::from_string(String::from("ms")).expect_err("parsing \"ms\" should fail");
@@ -562,6 +1157,71 @@ mod tests {
             .expect_err("Should have failed with invalid format");
     }
 
+    #[test]
+    fn test_from_string_unknown_unit_position() {
+        let result = DurationString::try_from(String::from("1h30x15s"));
+        assert_eq!(
+            result,
+            Err(Error::UnknownUnit {
+                unit: String::from("x"),
+                position: 4,
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_string_unknown_unit_display() {
+        let err = Error::UnknownUnit {
+            unit: String::from("x"),
+            position: 4,
+        };
+        assert_eq!(err.to_string(), "unknown time unit \"x\" at position 4");
+    }
+
+    #[test]
+    fn test_from_string_missing_unit_position() {
+        let result = DurationString::try_from(String::from("1h30"));
+        assert_eq!(result, Err(Error::MissingUnit { position: 4 }));
+    }
+
+    #[test]
+    fn test_from_string_fractional_hours() {
+        test_parse_string("1.5h", Duration::from_secs(5400));
+    }
+
+    #[test]
+    fn test_from_string_fractional_days() {
+        test_parse_string("0.25d", Duration::from_secs(21_600));
+    }
+
+    #[test]
+    fn test_from_string_fractional_seconds() {
+        test_parse_string("2.5s", Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn test_from_string_fractional_and_integer_combined() {
+        test_parse_string("1h0.5h", Duration::from_secs(5400));
+    }
+
+    #[test]
+    fn test_from_string_multiple_dots_is_error() {
+        DurationString::try_from(String::from("1.2.3s"))
+            .expect_err("multiple decimal points should fail");
+    }
+
+    #[test]
+    fn test_from_string_trailing_dot_is_error() {
+        DurationString::try_from(String::from("1.s"))
+            .expect_err("a trailing decimal point should fail");
+    }
+
+    #[test]
+    fn test_from_string_leading_dot_is_error() {
+        DurationString::try_from(String::from(".5s"))
+            .expect_err("a leading decimal point should fail");
+    }
+
     #[test]
     fn test_try_from_string_overflow_y() {
         let result = DurationString::try_from(String::from("584554530873y"));
@@ -573,4 +1233,123 @@ mod tests {
         let result = DurationString::try_from(String::from("584554530872y 29w"));
         assert_eq!(result, Err(Error::Overflow));
     }
+
+    #[test]
+    fn test_from_iso8601_date_and_time() {
+        let d = DurationString::from_iso8601("P1DT2H3M4S").unwrap();
+        assert_eq!(
+            Duration::from(d),
+            Duration::from_secs(86_400 + 2 * 3600 + 3 * 60 + 4)
+        );
+    }
+
+    #[test]
+    fn test_from_iso8601_time_only() {
+        let d = DurationString::from_iso8601("PT1H30M").unwrap();
+        assert_eq!(Duration::from(d), Duration::from_secs(5400));
+    }
+
+    #[test]
+    fn test_from_iso8601_date_only() {
+        let d = DurationString::from_iso8601("P1Y").unwrap();
+        assert_eq!(Duration::from(d), Duration::from_secs(31_556_926));
+    }
+
+    #[test]
+    fn test_from_iso8601_fractional_seconds() {
+        let d = DurationString::from_iso8601("PT1.5S").unwrap();
+        assert_eq!(Duration::from(d), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_from_iso8601_bare_p_is_error() {
+        DurationString::from_iso8601("P").expect_err("bare \"P\" should fail");
+    }
+
+    #[test]
+    fn test_from_iso8601_empty_time_is_error() {
+        DurationString::from_iso8601("P1DT").expect_err("empty time segment should fail");
+    }
+
+    #[test]
+    fn test_from_iso8601_wrong_order_is_error() {
+        DurationString::from_iso8601("P1D1Y").expect_err("out of order components should fail");
+    }
+
+    #[test]
+    fn test_from_iso8601_minutes_before_t_means_months() {
+        let d = DurationString::from_iso8601("P1M").unwrap();
+        assert_eq!(Duration::from(d), Duration::from_nanos(MONTH_IN_NANO as u64));
+    }
+
+    #[test]
+    fn test_from_iso8601_fraction_only_allowed_on_seconds() {
+        DurationString::from_iso8601("P1.5Y").expect_err("fractional years should fail");
+        DurationString::from_iso8601("PT1.5H").expect_err("fractional hours should fail");
+        DurationString::from_iso8601("P1.5D").expect_err("fractional days should fail");
+    }
+
+    #[test]
+    fn test_from_iso8601_malformed_fraction_is_error() {
+        DurationString::from_iso8601("PT1.S").expect_err("trailing dot should fail");
+        DurationString::from_iso8601("PT.5S").expect_err("leading dot should fail");
+    }
+
+    #[test]
+    fn test_to_iso8601_zero() {
+        let d = DurationString::new(Duration::from_secs(0));
+        assert_eq!(d.to_iso8601(), "PT0S");
+    }
+
+    #[test]
+    fn test_to_iso8601_round_trip() {
+        let d = DurationString::from_iso8601("P1DT2H3M4S").unwrap();
+        assert_eq!(d.to_iso8601(), "P1DT2H3M4S");
+    }
+
+    #[test]
+    fn test_to_iso8601_fractional_seconds() {
+        let d = DurationString::from_iso8601("PT1.5S").unwrap();
+        assert_eq!(d.to_iso8601(), "PT1.5S");
+    }
+
+    #[test]
+    fn test_to_string_compact() {
+        let d = DurationString::new(Duration::from_secs(5415));
+        assert_eq!(d.to_string_compact(), "1h30m15s");
+    }
+
+    #[test]
+    fn test_to_string_compact_round_trips() {
+        let d = DurationString::new(Duration::from_secs(5415));
+        assert_eq!(
+            d.to_string_compact().parse::<DurationString>().unwrap(),
+            d
+        );
+    }
+
+    #[test]
+    fn test_to_string_compact_zero() {
+        let d = DurationString::new(Duration::from_secs(0));
+        assert_eq!(d.to_string_compact(), "0ns");
+    }
+
+    #[test]
+    fn test_format_components_max_components() {
+        let d = DurationString::new(Duration::from_secs(5415));
+        assert_eq!(d.format_components(2, Unit::M), "1h30m");
+    }
+
+    #[test]
+    fn test_format_components_min_unit_truncates() {
+        let d = DurationString::new(Duration::from_millis(1500));
+        assert_eq!(d.format_components(usize::MAX, Unit::S), "1s");
+    }
+
+    #[test]
+    fn test_unit_ordering() {
+        assert!(Unit::Ns < Unit::S);
+        assert!(Unit::S < Unit::M);
+        assert!(Unit::Y > Unit::W);
+    }
 }